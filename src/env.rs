@@ -0,0 +1,198 @@
+//! Typed access to the environment variables Cargo sets for build scripts.
+//!
+//! <https://doc.rust-lang.org/cargo/reference/environment-variables.html#environment-variables-cargo-sets-for-build-scripts>
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::PathBuf;
+
+/// A source of environment variables, injectable so [`Env`] can be tested without touching the
+/// real process environment, matching the [`crate::core::Out::Buffer`] testing style.
+pub trait Source {
+    /// Looks up a single variable by name.
+    fn get(&self, key: &str) -> Option<String>;
+}
+
+impl<F: Fn(&str) -> Option<String>> Source for F {
+    fn get(&self, key: &str) -> Option<String> {
+        self(key)
+    }
+}
+
+impl Source for HashMap<&str, &str> {
+    fn get(&self, key: &str) -> Option<String> {
+        HashMap::get(self, key).map(|value| value.to_string())
+    }
+}
+
+/// Reads variables directly from the real process environment via [`std::env::var`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ProcessEnv;
+
+impl Source for ProcessEnv {
+    fn get(&self, key: &str) -> Option<String> {
+        std::env::var(key).ok()
+    }
+}
+
+/// Typed accessors for the environment variables Cargo sets for build scripts.
+///
+/// Defaults to reading the real process environment; use [`Env::with_source`] to read from a map
+/// or closure instead, e.g. in tests.
+///
+/// ```
+/// # use build_instructions::env::Env;
+/// # use std::collections::HashMap;
+/// let vars = HashMap::from([("TARGET", "x86_64-unknown-linux-gnu")]);
+/// let env = Env::with_source(vars);
+/// assert_eq!(env.target(), "x86_64-unknown-linux-gnu");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Env<S = ProcessEnv> {
+    source: S,
+}
+
+impl Default for Env<ProcessEnv> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Env<ProcessEnv> {
+    /// Creates an [`Env`] that reads from the real process environment.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self { source: ProcessEnv }
+    }
+}
+
+impl<S: Source> Env<S> {
+    /// Creates an [`Env`] that reads from a custom [`Source`], such as a `HashMap` or closure.
+    #[inline(always)]
+    pub fn with_source(source: S) -> Self {
+        Self { source }
+    }
+
+    /// `OUT_DIR`, the folder in which all output and intermediate artifacts should be placed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `OUT_DIR` isn't set, which shouldn't happen when run from a build script.
+    pub fn out_dir(&self) -> PathBuf {
+        PathBuf::from(self.source.get("OUT_DIR").expect("OUT_DIR is not set"))
+    }
+
+    /// `TARGET`, the target triple that is being compiled for.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `TARGET` isn't set, which shouldn't happen when run from a build script.
+    pub fn target(&self) -> String {
+        self.source.get("TARGET").expect("TARGET is not set")
+    }
+
+    /// `HOST`, the host triple of the rustc compiler.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `HOST` isn't set, which shouldn't happen when run from a build script.
+    pub fn host(&self) -> String {
+        self.source.get("HOST").expect("HOST is not set")
+    }
+
+    /// `PROFILE`, either `release` or `debug`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `PROFILE` isn't set, which shouldn't happen when run from a build script.
+    pub fn profile(&self) -> String {
+        self.source.get("PROFILE").expect("PROFILE is not set")
+    }
+
+    /// `CARGO_MANIFEST_DIR`, the directory containing the manifest of the package being built.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `CARGO_MANIFEST_DIR` isn't set, which shouldn't happen when run from a build
+    /// script.
+    pub fn manifest_dir(&self) -> PathBuf {
+        PathBuf::from(self.source.get("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR is not set"))
+    }
+
+    /// `CARGO_MANIFEST_LINKS`, the `links` value from the manifest, if it was set.
+    pub fn manifest_links(&self) -> Option<String> {
+        self.source.get("CARGO_MANIFEST_LINKS")
+    }
+
+    /// `CARGO_CFG_<name>`, the compile-time cfg values of `name`, if it was set.
+    ///
+    /// Values are split on `,`, matching the comma-separated list Cargo sets for multi-valued
+    /// cfgs (e.g. `CARGO_CFG_TARGET_FEATURE`).
+    pub fn cfg(&self, name: impl Display) -> Option<Vec<String>> {
+        let key = format!("CARGO_CFG_{}", name.to_string().to_uppercase());
+        self.source.get(&key).map(|value| value.split(',').map(str::to_string).collect())
+    }
+
+    /// `DEP_<LINKS>_<KEY>`, metadata emitted by the `links` package `links` via
+    /// [`crate::raw::Cargo::metadata`], if it was set.
+    pub fn dep_metadata(&self, links: impl Display, key: impl Display) -> Option<String> {
+        let screaming_snake_case = |s: String| s.to_uppercase().replace('-', "_");
+        let key =
+            format!("DEP_{}_{}", screaming_snake_case(links.to_string()), screaming_snake_case(key.to_string()));
+        self.source.get(&key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env_with(vars: &'static [(&'static str, &'static str)]) -> Env<HashMap<&'static str, &'static str>> {
+        Env::with_source(vars.iter().copied().collect())
+    }
+
+    #[test]
+    fn out_dir() {
+        let env = env_with(&[("OUT_DIR", "/tmp/out")]);
+        assert_eq!(env.out_dir(), PathBuf::from("/tmp/out"));
+    }
+
+    #[test]
+    fn target() {
+        let env = env_with(&[("TARGET", "x86_64-unknown-linux-gnu")]);
+        assert_eq!(env.target(), "x86_64-unknown-linux-gnu");
+    }
+
+    #[test]
+    fn manifest_links_missing() {
+        let env = env_with(&[]);
+        assert_eq!(env.manifest_links(), None);
+    }
+
+    #[test]
+    fn cfg_splits_values() {
+        let env = env_with(&[("CARGO_CFG_TARGET_FEATURE", "sse,sse2,avx")]);
+        assert_eq!(env.cfg("target_feature"), Some(vec!["sse".to_string(), "sse2".to_string(), "avx".to_string()]));
+        assert_eq!(env.cfg("unix"), None);
+    }
+
+    #[test]
+    fn dep_metadata() {
+        let env = env_with(&[("DEP_ZLIB_INCLUDE", "/usr/include")]);
+        assert_eq!(env.dep_metadata("zlib", "include"), Some("/usr/include".to_string()));
+        assert_eq!(env.dep_metadata("zlib", "missing"), None);
+    }
+
+    #[test]
+    fn dep_metadata_hyphenated_key() {
+        let env = env_with(&[("DEP_FOO_INCLUDE_DIR", "/usr/include")]);
+        assert_eq!(env.dep_metadata("foo", "include-dir"), Some("/usr/include".to_string()));
+    }
+
+    #[test]
+    fn closure_source() {
+        let env = Env::with_source(|key: &str| (key == "TARGET").then(|| "wasm32-unknown-unknown".to_string()));
+        assert_eq!(env.target(), "wasm32-unknown-unknown");
+    }
+}
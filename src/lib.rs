@@ -2,6 +2,8 @@ use std::fmt::Display;
 use std::path::Path;
 
 pub mod core;
+pub mod env;
+pub mod parse;
 pub mod raw;
 
 type Result = std::io::Result<()>;
@@ -133,6 +135,14 @@ impl Cargo {
         todo!()
     }
 
+    /// Registers a `cfg` name and its expected values, so Cargo doesn't warn on an unexpected
+    /// `--cfg` passed by [`Cargo::rustc_cfg`].
+    ///
+    /// <https://doc.rust-lang.org/cargo/reference/build-scripts.html#rustc-check-cfg>
+    pub fn rustc_check_cfg(&mut self, check_cfg: &raw::CheckCfg) -> Result {
+        self.inner.rustc_check_cfg(check_cfg)
+    }
+
     /// Passes custom flags to a linker for cdylib crates.
     ///
     /// <https://doc.rust-lang.org/cargo/reference/build-scripts.html#rustc-cdylib-link-arg>
@@ -0,0 +1,352 @@
+//! Parses Cargo build-script output, the inverse of [`crate::raw::Cargo`]'s emitters.
+//!
+//! <https://doc.rust-lang.org/cargo/reference/build-scripts.html#outputs-of-the-build-script>
+
+use std::io::{BufRead, Lines};
+
+/// The well-known identifiers accepted before the `=` in `rustc-link-search`.
+const LINK_SEARCH_KINDS: &[&str] = &["dependency", "crate", "native", "framework", "all"];
+
+/// A single build-script instruction, parsed from a `cargo:` or `cargo::` line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Instruction {
+    /// `rerun-if-changed=PATH`
+    RerunIfChanged(String),
+
+    /// `rerun-if-env-changed=VAR`
+    RerunIfEnvChanged(String),
+
+    /// Any of the `rustc-link-arg*` instructions, identified by [`LinkArgTarget`].
+    LinkArg(LinkArgTarget, String),
+
+    /// `rustc-link-lib=LIB`
+    LinkLib(String),
+
+    /// `rustc-link-search[=KIND]=PATH`
+    LinkSearch {
+        /// The search kind, e.g. `native` or `framework`, if one was given.
+        kind: Option<String>,
+
+        /// The path added to the search path.
+        path: String,
+    },
+
+    /// `rustc-flags=FLAGS`
+    Flags(String),
+
+    /// `rustc-cfg=KEY[=VALUE]`
+    Cfg {
+        /// The cfg name.
+        key: String,
+
+        /// The cfg value, if one was given.
+        value: Option<String>,
+    },
+
+    /// `rustc-env=VAR=VALUE`
+    Env {
+        /// The environment variable name.
+        var: String,
+
+        /// The environment variable value.
+        value: String,
+    },
+
+    /// `rustc-check-cfg=EXPRESSION`, where `EXPRESSION` is the raw `cfg(...)` expression.
+    CheckCfg(String),
+
+    /// `warning=MESSAGE`
+    Warning(String),
+
+    /// Arbitrary metadata: either legacy `cargo:KEY=VALUE` or namespaced `cargo::metadata=KEY=VALUE`.
+    Metadata {
+        /// The metadata key.
+        key: String,
+
+        /// The metadata value.
+        value: String,
+    },
+}
+
+/// Which target a [`Instruction::LinkArg`] instruction applies to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkArgTarget {
+    /// `rustc-link-arg`, applies to benchmarks, binaries, cdylib crates, examples, and tests.
+    All,
+
+    /// `rustc-link-arg-bin=BIN=FLAG`
+    Bin(String),
+
+    /// `rustc-link-arg-bins`
+    Bins,
+
+    /// `rustc-link-arg-tests`
+    Tests,
+
+    /// `rustc-link-arg-examples`
+    Examples,
+
+    /// `rustc-link-arg-benches`
+    Benches,
+
+    /// `rustc-cdylib-link-arg`
+    Cdylib,
+}
+
+/// Split `s` at the first `=`, if any, into `(before, after)`.
+fn split_once_eq(s: &str) -> (&str, Option<&str>) {
+    match s.split_once('=') {
+        Some((before, after)) => (before, Some(after)),
+        None => (s, None),
+    }
+}
+
+/// Parses a single line of build-script output, stripping either the `cargo:` or `cargo::` prefix.
+///
+/// Returns `None` if the line doesn't start with a recognized prefix, or if a `cargo::` line
+/// doesn't carry a known instruction name (unlike `cargo:`, `cargo::` has no legacy bare-metadata
+/// fallback).
+pub fn parse_line(line: &str) -> Option<Instruction> {
+    if let Some(rest) = line.strip_prefix("cargo::") {
+        let (name, payload) = split_once_eq(rest);
+        let payload = payload?;
+        double_colon(name, payload)
+    } else if let Some(rest) = line.strip_prefix("cargo:") {
+        let (name, payload) = split_once_eq(rest);
+        payload.and_then(|payload| colon(name, payload))
+    } else {
+        None
+    }
+}
+
+/// Parses the `name=payload` body of a `cargo::` line.
+fn double_colon(name: &str, payload: &str) -> Option<Instruction> {
+    match name {
+        "metadata" => {
+            let (key, value) = split_once_eq(payload);
+            Some(Instruction::Metadata { key: key.to_string(), value: value?.to_string() })
+        }
+        _ => reserved(name, payload),
+    }
+}
+
+/// Parses the `name=payload` body of a `cargo:` line, falling back to [`Instruction::Metadata`]
+/// for any name that isn't a recognized reserved instruction. A reserved name with a malformed
+/// payload yields `None`.
+fn colon(name: &str, payload: &str) -> Option<Instruction> {
+    match reserved(name, payload) {
+        Some(instruction) => Some(instruction),
+        // the only reserved names `reserved` can reject, for a payload missing its second `=`
+        None if matches!(name, "rustc-link-arg-bin" | "rustc-env") => None,
+        None => Some(Instruction::Metadata { key: name.to_string(), value: payload.to_string() }),
+    }
+}
+
+/// Parses a reserved instruction name common to both syntaxes.
+fn reserved(name: &str, payload: &str) -> Option<Instruction> {
+    Some(match name {
+        "rerun-if-changed" => Instruction::RerunIfChanged(payload.to_string()),
+        "rerun-if-env-changed" => Instruction::RerunIfEnvChanged(payload.to_string()),
+        "rustc-link-arg" => Instruction::LinkArg(LinkArgTarget::All, payload.to_string()),
+        "rustc-link-arg-bin" => {
+            let (bin, flag) = split_once_eq(payload);
+            Instruction::LinkArg(LinkArgTarget::Bin(bin.to_string()), flag?.to_string())
+        }
+        "rustc-link-arg-bins" => Instruction::LinkArg(LinkArgTarget::Bins, payload.to_string()),
+        "rustc-link-arg-tests" => Instruction::LinkArg(LinkArgTarget::Tests, payload.to_string()),
+        "rustc-link-arg-examples" => Instruction::LinkArg(LinkArgTarget::Examples, payload.to_string()),
+        "rustc-link-arg-benches" => Instruction::LinkArg(LinkArgTarget::Benches, payload.to_string()),
+        "rustc-cdylib-link-arg" => Instruction::LinkArg(LinkArgTarget::Cdylib, payload.to_string()),
+        "rustc-link-lib" => Instruction::LinkLib(payload.to_string()),
+        "rustc-link-search" => {
+            let (first, rest) = split_once_eq(payload);
+            match rest {
+                Some(path) if LINK_SEARCH_KINDS.contains(&first) => {
+                    Instruction::LinkSearch { kind: Some(first.to_string()), path: path.to_string() }
+                }
+                _ => Instruction::LinkSearch { kind: None, path: payload.to_string() },
+            }
+        }
+        "rustc-flags" => Instruction::Flags(payload.to_string()),
+        "rustc-cfg" => {
+            let (key, value) = split_once_eq(payload);
+            Instruction::Cfg { key: key.to_string(), value: value.map(str::to_string) }
+        }
+        "rustc-env" => {
+            let (var, value) = split_once_eq(payload);
+            Instruction::Env { var: var.to_string(), value: value?.to_string() }
+        }
+        "rustc-check-cfg" => Instruction::CheckCfg(payload.to_string()),
+        "warning" => Instruction::Warning(payload.to_string()),
+        _ => return None,
+    })
+}
+
+/// Parses every line of `input`, silently skipping lines that aren't recognized instructions.
+pub fn parse_str(input: &str) -> impl Iterator<Item = Instruction> + '_ {
+    input.lines().filter_map(parse_line)
+}
+
+/// Parses build-script output read line-by-line from `reader`.
+pub fn parse(reader: impl BufRead) -> Instructions<impl BufRead> {
+    Instructions { lines: reader.lines() }
+}
+
+/// An [`Iterator`] of parsed [`Instruction`]s, yielded from a [`BufRead`] source.
+///
+/// Lines that aren't recognized instructions are silently skipped, mirroring [`parse_str`].
+pub struct Instructions<R> {
+    lines: Lines<R>,
+}
+
+impl<R: BufRead> Iterator for Instructions<R> {
+    type Item = std::io::Result<Instruction>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            return match self.lines.next()? {
+                Ok(line) => match parse_line(&line) {
+                    Some(instruction) => Some(Ok(instruction)),
+                    None => continue,
+                },
+                Err(err) => Some(Err(err)),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rerun_if_changed() {
+        assert_eq!(parse_line("cargo:rerun-if-changed=asdf.txt"), Some(Instruction::RerunIfChanged("asdf.txt".to_string())));
+    }
+
+    #[test]
+    fn rerun_if_env_changed() {
+        assert_eq!(
+            parse_line("cargo:rerun-if-env-changed=ASDF"),
+            Some(Instruction::RerunIfEnvChanged("ASDF".to_string()))
+        );
+    }
+
+    #[test]
+    fn link_arg() {
+        assert_eq!(
+            parse_line("cargo:rustc-link-arg=-static"),
+            Some(Instruction::LinkArg(LinkArgTarget::All, "-static".to_string()))
+        );
+    }
+
+    #[test]
+    fn link_arg_bin() {
+        assert_eq!(
+            parse_line("cargo:rustc-link-arg-bin=cli=-static"),
+            Some(Instruction::LinkArg(LinkArgTarget::Bin("cli".to_string()), "-static".to_string()))
+        );
+    }
+
+    #[test]
+    fn link_lib() {
+        assert_eq!(
+            parse_line("cargo:rustc-link-lib=static:+whole-archive=mylib"),
+            Some(Instruction::LinkLib("static:+whole-archive=mylib".to_string()))
+        );
+    }
+
+    #[test]
+    fn link_search() {
+        assert_eq!(
+            parse_line("cargo:rustc-link-search=mylib"),
+            Some(Instruction::LinkSearch { kind: None, path: "mylib".to_string() })
+        );
+        assert_eq!(
+            parse_line("cargo:rustc-link-search=native=mylib"),
+            Some(Instruction::LinkSearch { kind: Some("native".to_string()), path: "mylib".to_string() })
+        );
+    }
+
+    #[test]
+    fn cfg() {
+        assert_eq!(
+            parse_line("cargo:rustc-cfg=asdf=hjkl"),
+            Some(Instruction::Cfg { key: "asdf".to_string(), value: Some("hjkl".to_string()) })
+        );
+        assert_eq!(parse_line("cargo:rustc-cfg=asdf"), Some(Instruction::Cfg { key: "asdf".to_string(), value: None }));
+    }
+
+    #[test]
+    fn env() {
+        assert_eq!(
+            parse_line("cargo:rustc-env=EDITOR=vim"),
+            Some(Instruction::Env { var: "EDITOR".to_string(), value: "vim".to_string() })
+        );
+    }
+
+    #[test]
+    fn check_cfg() {
+        assert_eq!(
+            parse_line(r#"cargo::rustc-check-cfg=cfg(asdf, values("a", "b"))"#),
+            Some(Instruction::CheckCfg(r#"cfg(asdf, values("a", "b"))"#.to_string()))
+        );
+    }
+
+    #[test]
+    fn warning() {
+        assert_eq!(parse_line("cargo:warning=teapot"), Some(Instruction::Warning("teapot".to_string())));
+    }
+
+    #[test]
+    fn metadata_colon() {
+        assert_eq!(
+            parse_line("cargo:asdf=hjkl"),
+            Some(Instruction::Metadata { key: "asdf".to_string(), value: "hjkl".to_string() })
+        );
+    }
+
+    #[test]
+    fn metadata_double_colon() {
+        assert_eq!(
+            parse_line("cargo::metadata=asdf=hjkl"),
+            Some(Instruction::Metadata { key: "asdf".to_string(), value: "hjkl".to_string() })
+        );
+    }
+
+    #[test]
+    fn unrecognized_double_colon_is_skipped() {
+        assert_eq!(parse_line("cargo::not-a-real-instruction=value"), None);
+    }
+
+    #[test]
+    fn not_cargo_output_is_skipped() {
+        assert_eq!(parse_line("just some build script chatter"), None);
+    }
+
+    #[test]
+    fn parse_str_skips_unrecognized_lines() {
+        let output = "cargo:rerun-if-changed=asdf.txt\nnoise\ncargo::rustc-link-lib=mylib\n";
+        let parsed: Vec<_> = parse_str(output).collect();
+        assert_eq!(
+            parsed,
+            vec![
+                Instruction::RerunIfChanged("asdf.txt".to_string()),
+                Instruction::LinkLib("mylib".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_buf_read() {
+        let output = b"cargo:rustc-env=EDITOR=vim\ncargo:warning=teapot\n" as &[u8];
+        let parsed: Vec<_> = parse(output).map(Result::unwrap).collect();
+        assert_eq!(
+            parsed,
+            vec![
+                Instruction::Env { var: "EDITOR".to_string(), value: "vim".to_string() },
+                Instruction::Warning("teapot".to_string()),
+            ]
+        );
+    }
+}
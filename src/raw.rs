@@ -4,19 +4,104 @@ use std::borrow::Cow;
 use std::fmt::Display;
 use std::io::Write;
 
-use crate::core::Prefix;
+use crate::core::{Out, Prefix};
 
 type Result = std::io::Result<()>;
 
+/// Which directive syntax Cargo build script output is written in.
+///
+/// Cargo 1.77 introduced a double-colon `cargo::` syntax for structured instructions, alongside
+/// the original single-colon `cargo:` syntax. Bare `cargo:KEY=VALUE` metadata is only recognized
+/// as such when the package sets `links`; arbitrary metadata must otherwise be namespaced as
+/// `cargo::metadata=KEY=VALUE`.
+///
+/// <https://doc.rust-lang.org/cargo/reference/build-scripts.html#outputs-of-the-build-script>
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Syntax {
+    /// The original `cargo:` prefix.
+    #[default]
+    Colon,
+
+    /// The `cargo::` prefix introduced in Cargo 1.77, required for `cargo::metadata=KEY=VALUE`.
+    DoubleColon,
+}
+
+impl Syntax {
+    /// The literal prefix written before every instruction name.
+    const fn prefix(self) -> &'static str {
+        match self {
+            Self::Colon => "cargo:",
+            Self::DoubleColon => "cargo::",
+        }
+    }
+}
+
+/// A `cfg(name, values(...))` expression for [`Cargo::rustc_check_cfg`].
+///
+/// <https://doc.rust-lang.org/cargo/reference/build-scripts.html#rustc-check-cfg>
+///
+/// ```
+/// # use build_instructions::raw::CheckCfg;
+/// assert_eq!(CheckCfg::new("asdf").to_string(), "cfg(asdf)");
+/// assert_eq!(CheckCfg::new("asdf").values(["a", "b"]).to_string(), r#"cfg(asdf, values("a", "b"))"#);
+/// assert_eq!(CheckCfg::new("asdf").bare(true).to_string(), "cfg(asdf, values(none()))");
+/// ```
+#[derive(Debug, Clone)]
+pub struct CheckCfg {
+    name: Cow<'static, str>,
+    values: Vec<Cow<'static, str>>,
+    bare: bool,
+}
+
+impl CheckCfg {
+    /// Creates a [`CheckCfg`] for `name` with no expected values and no bare `cfg(name)` form.
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self { name: name.into(), values: Vec::new(), bare: false }
+    }
+
+    /// Sets the expected string values for this cfg, e.g. `values(["fast", "slow"])`.
+    pub fn values(mut self, values: impl IntoIterator<Item = impl Into<Cow<'static, str>>>) -> Self {
+        self.values = values.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Allows the bare `cfg(name)` form with no value, alongside any [`CheckCfg::values`].
+    pub fn bare(mut self, bare: bool) -> Self {
+        self.bare = bare;
+        self
+    }
+}
+
+impl Display for CheckCfg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cfg({}", self.name)?;
+        if self.bare || !self.values.is_empty() {
+            write!(f, ", values(")?;
+            let mut rest = false;
+            if self.bare {
+                write!(f, "none()")?;
+                rest = true;
+            }
+            for value in &self.values {
+                write!(f, "{}{value:?}", if rest { ", " } else { "" })?;
+                rest = true;
+            }
+            write!(f, ")")?;
+        }
+        write!(f, ")")
+    }
+}
+
 #[derive(Debug)]
 pub struct Cargo {
     inner: Prefix,
+    syntax: Syntax,
 }
 
 impl Default for Cargo {
     #[inline(always)]
     fn default() -> Self {
-        Self { inner: Prefix { prefix: Cow::Borrowed("cargo:"), ..Default::default() } }
+        Self::new(Out::default())
     }
 }
 
@@ -28,21 +113,44 @@ impl AsRef<Prefix> for Cargo {
 }
 
 /// Write content to [`Out`], automatically handling implementation details.
+///
+/// Writes behind `$self.inner.prefix` by default; pass an explicit `$prefix` expression for
+/// instructions that require a fixed prefix regardless of the configured [`Syntax`].
 macro_rules! out {
-    ($self:ident, $($arg:tt)*) => {{
+    ($self:ident, $prefix:expr, $($arg:tt)*) => {{
         let out = &mut $self.inner.out;
         let lock = out.lock();
 
-        write!(out, "{}", $self.inner.prefix)?;
+        write!(out, "{}", $prefix)?;
         writeln!(out, $($arg)*)?;
         drop(lock);
         out.flush()?;
 
         Ok(())
+    }};
+    ($self:ident, $($arg:tt)*) => {{
+        out!($self, $self.inner.prefix, $($arg)*)
     }}
 }
 
 impl Cargo {
+    /// Creates a new [`Cargo`] that writes to `out` using the legacy [`Syntax::Colon`] prefix.
+    #[inline(always)]
+    pub fn new(out: Out) -> Self {
+        Self::with_syntax(out, Syntax::Colon)
+    }
+
+    /// Creates a new [`Cargo`] that writes to `out` using the given [`Syntax`].
+    pub fn with_syntax(out: Out, syntax: Syntax) -> Self {
+        Self { inner: Prefix { prefix: Cow::Borrowed(syntax.prefix()), out }, syntax }
+    }
+
+    /// The [`Syntax`] this [`Cargo`] emits instructions with.
+    #[inline(always)]
+    pub fn syntax(&self) -> Syntax {
+        self.syntax
+    }
+
     /// Turn [`Cargo`] into the [`Prefix`] it was wrapping.
     #[inline(always)]
     pub fn into_inner(self) -> Prefix {
@@ -146,6 +254,17 @@ impl Cargo {
         out!(self, "rustc-env={var}={value}")
     }
 
+    /// Registers a `cfg` name and its expected values, so Cargo doesn't warn on an unexpected
+    /// `--cfg` passed by [`Cargo::rustc_cfg`].
+    ///
+    /// Always written with the `cargo::` prefix, as Cargo only recognizes `rustc-check-cfg` under
+    /// the double-colon syntax, regardless of this [`Cargo`]'s configured [`Syntax`].
+    ///
+    /// <https://doc.rust-lang.org/cargo/reference/build-scripts.html#rustc-check-cfg>
+    pub fn rustc_check_cfg(&mut self, check_cfg: &CheckCfg) -> Result {
+        out!(self, "cargo::", "rustc-check-cfg={check_cfg}")
+    }
+
     /// Passes custom flags to a linker for cdylib crates.
     ///
     /// <https://doc.rust-lang.org/cargo/reference/build-scripts.html#rustc-cdylib-link-arg>
@@ -162,9 +281,15 @@ impl Cargo {
 
     /// Metadata, used by links scripts.
     ///
+    /// Under [`Syntax::DoubleColon`] this is namespaced as `cargo::metadata=KEY=VALUE`, since
+    /// bare `cargo:KEY=VALUE` is only treated as metadata when the package sets `links`.
+    ///
     /// <https://doc.rust-lang.org/cargo/reference/build-scripts.html#the-links-manifest-key>
     pub fn metadata(&mut self, key: impl Display, value: impl Display) -> Result {
-        out!(self, "{key}={value}")
+        match self.syntax {
+            Syntax::Colon => out!(self, "{key}={value}"),
+            Syntax::DoubleColon => out!(self, "metadata={key}={value}"),
+        }
     }
 }
 
@@ -175,7 +300,12 @@ mod tests {
 
     /// Creates a [`Cargo`] that uses an in-memory buffer for output.
     fn cargo_buffer() -> Cargo {
-        Cargo { inner: Prefix { prefix: "cargo:".into(), out: Out::Buffer(Vec::new()) } }
+        Cargo::with_syntax(Out::Buffer(Vec::new()), Syntax::Colon)
+    }
+
+    /// Creates a [`Cargo`] that uses an in-memory buffer for output, under [`Syntax::DoubleColon`].
+    fn cargo_buffer_double_colon() -> Cargo {
+        Cargo::with_syntax(Out::Buffer(Vec::new()), Syntax::DoubleColon)
     }
 
     /// Grab the buffer as a [`String`].
@@ -311,4 +441,48 @@ mod tests {
         cargo.metadata("asdf", "hjkl").unwrap();
         assert_eq!(buffer_value(&cargo), "cargo:asdf=hjkl\n");
     }
+
+    #[test]
+    fn metadata_double_colon() {
+        let mut cargo = cargo_buffer_double_colon();
+        cargo.metadata("asdf", "hjkl").unwrap();
+        assert_eq!(buffer_value(&cargo), "cargo::metadata=asdf=hjkl\n");
+    }
+
+    #[test]
+    fn rustc_link_lib_double_colon() {
+        let mut cargo = cargo_buffer_double_colon();
+        cargo.rustc_link_lib("mylib").unwrap();
+        assert_eq!(buffer_value(&cargo), "cargo::rustc-link-lib=mylib\n");
+    }
+
+    #[test]
+    fn rustc_check_cfg() {
+        let mut cargo = cargo_buffer();
+        cargo.rustc_check_cfg(&CheckCfg::new("asdf")).unwrap();
+        assert_eq!(buffer_value(&cargo), "cargo::rustc-check-cfg=cfg(asdf)\n");
+
+        let mut cargo = cargo_buffer();
+        cargo.rustc_check_cfg(&CheckCfg::new("asdf").values(["fast", "slow"])).unwrap();
+        assert_eq!(buffer_value(&cargo), "cargo::rustc-check-cfg=cfg(asdf, values(\"fast\", \"slow\"))\n");
+    }
+
+    #[test]
+    fn rustc_check_cfg_always_double_colon() {
+        // rustc-check-cfg requires the cargo:: prefix even when the rest of the output uses cargo:
+        let mut cargo = cargo_buffer();
+        cargo.rustc_check_cfg(&CheckCfg::new("asdf")).unwrap();
+        assert!(buffer_value(&cargo).starts_with("cargo::"));
+    }
+
+    #[test]
+    fn check_cfg_display() {
+        assert_eq!(CheckCfg::new("asdf").to_string(), "cfg(asdf)");
+        assert_eq!(CheckCfg::new("asdf").values(["a", "b"]).to_string(), r#"cfg(asdf, values("a", "b"))"#);
+        assert_eq!(CheckCfg::new("asdf").bare(true).to_string(), "cfg(asdf, values(none()))");
+        assert_eq!(
+            CheckCfg::new("asdf").bare(true).values(["a"]).to_string(),
+            r#"cfg(asdf, values(none(), "a"))"#
+        );
+    }
 }